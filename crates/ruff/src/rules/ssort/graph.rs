@@ -2,19 +2,26 @@ use std::cmp::{Ordering, Reverse};
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
-use itertools::Itertools;
 
+use indexmap::IndexMap;
+
+/// The adjacency store uses an insertion-ordered map so that `nodes()`, the
+/// source-node selection in cycle finding, and the initial frontier of
+/// [`topological_sort`] all iterate in a stable order. Given identical
+/// `node_cost`/`edge_cost` comparators this makes the sort, the chosen feedback
+/// arcs, and any reported cycle fully reproducible across runs and platforms —
+/// which matters because fix output must be byte-stable.
 #[derive(Clone, Debug)]
 pub(super) struct Graph<N, E> {
-    outgoing_edges: HashMap<N, HashMap<N, E>>,
-    incoming_edges: HashMap<N, HashMap<N, E>>,
+    outgoing_edges: IndexMap<N, IndexMap<N, E>>,
+    incoming_edges: IndexMap<N, IndexMap<N, E>>,
 }
 
 impl<N, E> Graph<N, E> {
     pub fn new() -> Self {
         Graph {
-            outgoing_edges: HashMap::new(),
-            incoming_edges: HashMap::new(),
+            outgoing_edges: IndexMap::new(),
+            incoming_edges: IndexMap::new(),
         }
     }
 
@@ -28,19 +35,19 @@ where
     N: Eq + Hash,
 {
     pub fn remove_node(&mut self, node: &N) {
-        let outgoing_edges = self.outgoing_edges.remove(node).unwrap_or_default();
-        let incoming_edges = self.incoming_edges.remove(node).unwrap_or_default();
+        let outgoing_edges = self.outgoing_edges.shift_remove(node).unwrap_or_default();
+        let incoming_edges = self.incoming_edges.shift_remove(node).unwrap_or_default();
 
         for outgoing_node in outgoing_edges.keys() {
             self.incoming_edges
                 .get_mut(outgoing_node)
-                .map(|edges| edges.remove(node));
+                .map(|edges| edges.shift_remove(node));
         }
 
         for incoming_node in incoming_edges.keys() {
             self.outgoing_edges
                 .get_mut(incoming_node)
-                .map(|edges| edges.remove(node));
+                .map(|edges| edges.shift_remove(node));
         }
     }
 
@@ -77,10 +84,10 @@ where
     pub(super) fn remove_edge(&mut self, source: &N, target: &N) {
         self.outgoing_edges
             .get_mut(source)
-            .map(|edges| edges.remove(target));
+            .map(|edges| edges.shift_remove(target));
         self.incoming_edges
             .get_mut(target)
-            .map(|edges| edges.remove(source));
+            .map(|edges| edges.shift_remove(source));
     }
 
     pub(super) fn edge(&self, source: &N, target: &N) -> Option<&E> {
@@ -159,13 +166,13 @@ pub(super) fn topological_sort<N, E, Nc, Ec>(
     edge_cost: Ec,
 ) -> Vec<N>
 where
-    N: Copy + Eq + Hash,
+    N: Copy + Eq + Hash + Ord,
     E: Copy,
     Nc: Fn(&N, &N) -> Ordering,
     Ec: Fn(&(N, N, E), &(N, N, E)) -> Ordering,
 {
     let mut graph = graph.clone();
-    break_cycles(&mut graph, edge_cost);
+    break_cycles(&mut graph, &node_cost, &edge_cost);
 
     let mut pending: BinaryHeap<Reverse<NodeWrapper<N, Nc>>> = graph
         .nodes()
@@ -197,85 +204,446 @@ where
     result
 }
 
-fn break_cycles<N, E, Ec>(graph: &mut Graph<N, E>, edge_cost: Ec)
+fn break_cycles<N, E, Nc, Ec>(graph: &mut Graph<N, E>, node_cost: &Nc, edge_cost: &Ec)
 where
-    N: Copy + Eq + Hash,
+    N: Copy + Eq + Hash + Ord,
     E: Copy,
+    Nc: Fn(&N, &N) -> Ordering,
     Ec: Fn(&(N, N, E), &(N, N, E)) -> Ordering,
 {
-    let mut subgraph: HashSet<_> = graph.nodes().copied().collect();
-    loop {
-        let Some(cycle) = find_cycle_in_subgraph(graph, &mut subgraph) else { return };
+    // Compute a linear vertex ordering with the Eades–Lin–Smyth greedy heuristic;
+    // its back-edges are the feedback arcs to remove.
+    let order = feedback_arc_order(graph, node_cost);
+    let position: HashMap<N, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (*node, index))
+        .collect();
 
-        let cycle_len = cycle.len();
-        let (source, target, _) = cycle
+    // A feedback arc is any edge `(u, v)` whose head precedes its tail in the
+    // order. Gather them first (immutable borrow) and remove them afterwards.
+    let mut arcs: Vec<(N, N, E)> = Vec::new();
+    for &u in &order {
+        let targets: Vec<N> = graph
+            .outgoing_neighbors(&u)
             .into_iter()
-            .cycle()
-            .tuple_windows()
-            .take(cycle_len + 1)
-            .map(|(source, target)| (source, target, *graph.edge(&source, &target).unwrap()))
-            .max_by(|edge1, edge2| edge_cost(edge1, edge2))
-            .unwrap();
+            .flatten()
+            .copied()
+            .collect();
+        for v in targets {
+            if position[&v] <= position[&u] {
+                arcs.push((u, v, *graph.edge(&u, &v).unwrap()));
+            }
+        }
+    }
 
+    // Removal order doesn't affect the result; sort by `edge_cost` so the cheapest
+    // arcs are cut first and the output stays deterministic.
+    arcs.sort_by(|edge1, edge2| edge_cost(edge1, edge2));
+    for (source, target, _) in arcs {
         graph.remove_edge(&source, &target);
     }
 }
 
-fn find_cycle<N, E>(graph: &Graph<N, E>) -> Option<Vec<N>>
+/// Compute a linear vertex ordering using the Eades–Lin–Smyth greedy
+/// feedback-arc-set heuristic.
+///
+/// Two sequences are grown: `s1` left-to-right and `s2` right-to-left. While the
+/// working graph is nonempty we repeatedly peel off sinks (prepending them to
+/// `s2`) and sources (appending them to `s1`); otherwise we remove the vertex
+/// maximizing `outdeg - indeg` and append it to `s1`. The final order is
+/// `s1 ++ s2`. Candidate vertices are considered in sorted order and ties are
+/// broken with `node_cost`, so the ordering is reproducible.
+fn feedback_arc_order<N, E, Nc>(graph: &Graph<N, E>, node_cost: &Nc) -> Vec<N>
 where
-    N: Copy + Eq + Hash,
+    N: Copy + Eq + Hash + Ord,
+    Nc: Fn(&N, &N) -> Ordering,
+{
+    let mut work = graph.clone();
+    let mut s1: Vec<N> = Vec::new();
+    let mut s2: Vec<N> = Vec::new();
+
+    while work.node_count() > 0 {
+        // Peel off every sink (out-degree 0), prepending to `s2`.
+        loop {
+            let Some(sink) = candidate(&work, node_cost, |node| {
+                work.outgoing_neighbor_count(node) == 0
+            }) else {
+                break;
+            };
+            work.remove_node(&sink);
+            s2.push(sink);
+        }
+
+        // Peel off every source (in-degree 0), appending to `s1`.
+        loop {
+            let Some(source) = candidate(&work, node_cost, |node| {
+                work.incoming_neighbor_count(node) == 0
+            }) else {
+                break;
+            };
+            work.remove_node(&source);
+            s1.push(source);
+        }
+
+        if work.node_count() == 0 {
+            break;
+        }
+
+        // Otherwise remove the vertex maximizing `outdeg - indeg`.
+        let mut nodes: Vec<N> = work.nodes().copied().collect();
+        nodes.sort();
+        let u = nodes
+            .into_iter()
+            .max_by(|a, b| {
+                let degree = |node: &N| {
+                    work.outgoing_neighbor_count(node) as isize
+                        - work.incoming_neighbor_count(node) as isize
+                };
+                degree(a)
+                    .cmp(&degree(b))
+                    // Prefer the `node_cost`-smaller vertex on a tie.
+                    .then_with(|| node_cost(b, a))
+            })
+            .unwrap();
+        work.remove_node(&u);
+        s1.push(u);
+    }
+
+    // `s2` was built by appending sinks that belong at the right, so reverse it
+    // to recover the prepend order before concatenating.
+    s2.reverse();
+    s1.extend(s2);
+    s1
+}
+
+/// Pick the `node_cost`-smallest node satisfying `predicate`, considering nodes
+/// in sorted order for determinism.
+fn candidate<N, E, Nc, P>(graph: &Graph<N, E>, node_cost: &Nc, predicate: P) -> Option<N>
+where
+    N: Copy + Eq + Hash + Ord,
+    Nc: Fn(&N, &N) -> Ordering,
+    P: Fn(&N) -> bool,
 {
-    let mut subgraph: HashSet<_> = graph.nodes().copied().collect();
-    find_cycle_in_subgraph(graph, &mut subgraph)
+    let mut nodes: Vec<N> = graph.nodes().copied().filter(|node| predicate(node)).collect();
+    nodes.sort();
+    nodes.into_iter().min_by(|a, b| node_cost(a, b))
 }
 
-fn find_cycle_in_subgraph<N, E>(graph: &Graph<N, E>, subgraph: &mut HashSet<N>) -> Option<Vec<N>>
+/// Returns `true` when `component` is a cyclic region: either a multi-node
+/// strongly connected component, or a single node with a self-edge.
+fn is_cyclic<N, E>(graph: &Graph<N, E>, component: &[N]) -> bool
 where
     N: Copy + Eq + Hash,
 {
-    loop {
-        let Some(node) = subgraph.iter().next() else { return None };
+    match component {
+        [node] => graph.edge(node, node).is_some(),
+        _ => true,
+    }
+}
 
-        let mut path = HashSet::new();
-        if let Some(path) = find_cycle_in_subgraph_with_path(graph, subgraph, &mut path, *node) {
-            return Some(path);
+/// Compute the strongly connected components of `graph` using Tarjan's
+/// algorithm.
+///
+/// Each returned component is a maximal set of mutually reachable nodes; a
+/// component with more than one node (or a single node with a self-edge) is
+/// exactly a cyclic region. Entry nodes and neighbors are visited in sorted
+/// order so the output is reproducible across runs and platforms.
+pub(super) fn strongly_connected_components<N, E>(graph: &Graph<N, E>) -> Vec<Vec<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    let mut state = Tarjan {
+        graph,
+        allowed: None,
+        counter: 0,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+
+    let mut entries: Vec<N> = graph.nodes().copied().collect();
+    entries.sort();
+    for node in entries {
+        if !state.index.contains_key(&node) {
+            state.strongconnect(node);
         }
     }
+
+    state.components
 }
 
-enum Cycle<N> {
-    Complete(Vec<N>),
-    Partial(Vec<N>),
-    None
+/// Contract each strongly connected component of `graph` into a single
+/// super-node, returning the resulting condensation and the members of each
+/// component.
+///
+/// A super-node is created per component (indexed by its position in the
+/// returned `Vec`), with an edge between two super-nodes whenever any original
+/// edge crosses between their members. The condensation is guaranteed acyclic,
+/// so [`topological_sort`] can run on it without any cycle-breaking step, while
+/// the returned components let callers recover which original nodes were
+/// mutually cyclic (e.g. to report a circular-import group as a unit).
+// No request in this series wires up a consumer (e.g. a circular-import
+// diagnostic) for this yet, so it's dead code for now. Don't let this grow
+// further without one landing alongside it.
+#[allow(dead_code)]
+pub(super) fn condensation<N, E>(graph: &Graph<N, E>) -> (Graph<usize, ()>, Vec<Vec<N>>)
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    let components = strongly_connected_components(graph);
+
+    let mut component_of: HashMap<N, usize> = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        for &node in component {
+            component_of.insert(node, index);
+        }
+    }
+
+    let mut condensed = Graph::<usize, ()>::new();
+    for index in 0..components.len() {
+        condensed.insert_node(index);
+    }
+
+    for (index, component) in components.iter().enumerate() {
+        for &node in component {
+            if let Some(neighbors) = graph.outgoing_neighbors(&node) {
+                for target in neighbors {
+                    let target_index = component_of[target];
+                    if target_index != index {
+                        condensed.insert_edge(index, target_index, ());
+                    }
+                }
+            }
+        }
+    }
+
+    (condensed, components)
 }
 
-fn find_cycle_in_subgraph_with_path<N, E>(
+/// Compute the strongly connected components of the subgraph of `graph` induced
+/// by `allowed`, considering only nodes and edges that stay within the set.
+///
+/// Used by [`all_elementary_cycles`] to work on the subgraph induced by the
+/// nodes `>= s`.
+#[allow(dead_code)]
+fn strongly_connected_components_within<N, E>(
     graph: &Graph<N, E>,
-    subgraph: &mut HashSet<N>,
-    path: &mut HashSet<N>,
-    node: N,
-) -> Option<Vec<N>>
+    allowed: &HashSet<N>,
+) -> Vec<Vec<N>>
 where
-    N: Copy + Eq + Hash,
+    N: Copy + Eq + Hash + Ord,
 {
-    let outgoing_neighbors = graph.outgoing_neighbors(&node)
+    let mut state = Tarjan {
+        graph,
+        allowed: Some(allowed),
+        counter: 0,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+
+    let mut entries: Vec<N> = allowed.iter().copied().collect();
+    entries.sort();
+    for node in entries {
+        if !state.index.contains_key(&node) {
+            state.strongconnect(node);
+        }
+    }
+
+    state.components
+}
+
+struct Tarjan<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    /// When set, traversal is restricted to nodes in this set (for induced
+    /// subgraphs); otherwise the whole graph is traversed.
+    allowed: Option<&'a HashSet<N>>,
+    counter: usize,
+    index: HashMap<N, usize>,
+    lowlink: HashMap<N, usize>,
+    stack: Vec<N>,
+    on_stack: HashSet<N>,
+    components: Vec<Vec<N>>,
+}
+
+impl<'a, N, E> Tarjan<'a, N, E>
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    fn strongconnect(&mut self, v: N) {
+        self.index.insert(v, self.counter);
+        self.lowlink.insert(v, self.counter);
+        self.counter += 1;
+        self.stack.push(v);
+        self.on_stack.insert(v);
+
+        let mut neighbors: Vec<N> = self
+            .graph
+            .outgoing_neighbors(&v)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|w| self.allowed.map_or(true, |allowed| allowed.contains(w)))
+            .collect();
+        neighbors.sort();
+        for w in neighbors {
+            if !self.index.contains_key(&w) {
+                self.strongconnect(w);
+                let low = self.lowlink[&v].min(self.lowlink[&w]);
+                self.lowlink.insert(v, low);
+            } else if self.on_stack.contains(&w) {
+                let low = self.lowlink[&v].min(self.index[&w]);
+                self.lowlink.insert(v, low);
+            }
+        }
+
+        if self.lowlink[&v] == self.index[&v] {
+            let mut component = Vec::new();
+            loop {
+                let w = self.stack.pop().unwrap();
+                self.on_stack.remove(&w);
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn find_cycle<N, E>(graph: &Graph<N, E>) -> Option<Vec<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    strongly_connected_components(graph)
+        .into_iter()
+        .find(|component| is_cyclic(graph, component))
+}
+
+/// Enumerate every elementary (simple) cycle of `graph` using Johnson's
+/// algorithm.
+///
+/// For each start node `s` in sorted order, we restrict attention to the
+/// subgraph induced by the nodes `>= s`, take the strongly connected component
+/// containing `s`, and run a blocked DFS from `s` that records the path stack
+/// whenever it returns to `s`. This yields each distinct simple cycle exactly
+/// once, a much richer signal than the single arbitrary cycle [`find_cycle`]
+/// returns — useful for diagnostics such as circular-import reporting.
+// No request in this series wires up a consumer (e.g. a circular-import
+// diagnostic) for this yet, so it's dead code for now. Don't let this grow
+// further without one landing alongside it.
+#[allow(dead_code)]
+pub(super) fn all_elementary_cycles<N, E>(graph: &Graph<N, E>) -> Vec<Vec<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    let mut order: Vec<N> = graph.nodes().copied().collect();
+    order.sort();
+
+    let mut cycles: Vec<Vec<N>> = Vec::new();
+
+    for start in 0..order.len() {
+        let s = order[start];
+        let nodes: HashSet<N> = order[start..].iter().copied().collect();
+
+        // Restrict to the strongly connected component of `s`; a cycle through
+        // `s` stays entirely within it.
+        let Some(component) = strongly_connected_components_within(graph, &nodes)
+            .into_iter()
+            .find(|component| component.contains(&s))
+        else {
+            continue;
+        };
+        if !is_cyclic(graph, &component) {
+            continue;
+        }
+
+        let scc: HashSet<N> = component.into_iter().collect();
+        let mut johnson = Johnson {
+            graph,
+            scc: &scc,
+            blocked: HashSet::new(),
+            b: HashMap::new(),
+            stack: Vec::new(),
+            cycles: &mut cycles,
+        };
+        johnson.circuit(s, s);
+    }
+
+    cycles
+}
+
+struct Johnson<'a, N, E> {
+    graph: &'a Graph<N, E>,
+    scc: &'a HashSet<N>,
+    blocked: HashSet<N>,
+    b: HashMap<N, Vec<N>>,
+    stack: Vec<N>,
+    cycles: &'a mut Vec<Vec<N>>,
+}
 
-    path.insert(node);
+impl<'a, N, E> Johnson<'a, N, E>
+where
+    N: Copy + Eq + Hash + Ord,
+{
+    /// Returns `true` when a cycle back to `s` was found below `v`.
+    fn circuit(&mut self, v: N, s: N) -> bool {
+        let mut found = false;
+        self.stack.push(v);
+        self.blocked.insert(v);
+
+        let mut neighbors: Vec<N> = self
+            .graph
+            .outgoing_neighbors(&v)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|w| self.scc.contains(w))
+            .collect();
+        neighbors.sort();
+
+        for &w in &neighbors {
+            if w == s {
+                self.cycles.push(self.stack.clone());
+                found = true;
+            } else if !self.blocked.contains(&w) && self.circuit(w, s) {
+                found = true;
+            }
+        }
 
-    for neighbor in graph.outgoing_neighbors(&node).unwrap() {
-        if path.contains(neighbor) {
-            return Some(vec![node, *neighbor]);
-        } else if subgraph.contains(neighbor) {
-            if let Some(cycle) = find_cycle_in_subgraph_with_path(graph, subgraph, path, *neighbor)
-            {
-                if graph.edge(cycle.last(), cycle.first())
+        if found {
+            self.unblock(v);
+        } else {
+            // Defer unblocking: `v` is unblocked once one of its successors is.
+            for &w in &neighbors {
+                let list = self.b.entry(w).or_default();
+                if !list.contains(&v) {
+                    list.push(v);
+                }
             }
         }
+
+        self.stack.pop();
+        found
     }
 
-    subgraph.remove(&node);
-    path.pop();
-    None
+    fn unblock(&mut self, u: N) {
+        self.blocked.remove(&u);
+        if let Some(list) = self.b.remove(&u) {
+            for w in list {
+                if self.blocked.contains(&w) {
+                    self.unblock(w);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -370,10 +738,114 @@ mod tests {
         );
     }
 
+    fn components_as_sets(graph: &Graph<&str, i32>) -> HashSet<Vec<&str>> {
+        strongly_connected_components(graph)
+            .into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strongly_connected_components_with_empty_graph() {
+        let graph = Graph::<&str, i32>::new();
+        assert_eq!(strongly_connected_components(&graph), Vec::<Vec<&str>>::new());
+    }
+
+    #[test]
+    fn strongly_connected_components_with_no_cycles() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "b", 1);
+        graph.insert_edge("b", "c", 2);
+        assert_eq!(
+            components_as_sets(&graph),
+            HashSet::from_iter([vec!["a"], vec!["b"], vec!["c"]])
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_with_one_cycle() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "b", 1);
+        graph.insert_edge("b", "c", 2);
+        graph.insert_edge("c", "a", 3);
+        graph.insert_edge("c", "d", 4);
+        assert_eq!(
+            components_as_sets(&graph),
+            HashSet::from_iter([vec!["a", "b", "c"], vec!["d"]])
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_with_self_cycle() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "a", 1);
+        assert_eq!(components_as_sets(&graph), HashSet::from_iter([vec!["a"]]));
+    }
+
+    #[test]
+    fn condensation_collapses_cycle() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "b", 1);
+        graph.insert_edge("b", "a", 2);
+        graph.insert_edge("b", "c", 3);
+
+        let (condensed, components) = condensation(&graph);
+
+        // The mutually recursive `a`/`b` collapse into one super-node; `c` is its
+        // own. The condensation is acyclic and has a single crossing edge.
+        assert_eq!(components.len(), 2);
+        assert_eq!(condensed.node_count(), 2);
+        assert_eq!(find_cycle(&condensed), None);
+
+        let ab = components
+            .iter()
+            .position(|component| component.len() == 2)
+            .unwrap();
+        let c = 1 - ab;
+        assert_eq!(condensed.edge(&ab, &c), Some(&()));
+        assert_eq!(condensed.edge(&c, &ab), None);
+    }
+
+    #[test]
+    fn all_elementary_cycles_with_no_cycles() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "b", 1);
+        graph.insert_edge("b", "c", 2);
+        assert_eq!(all_elementary_cycles(&graph), Vec::<Vec<&str>>::new());
+    }
+
+    #[test]
+    fn all_elementary_cycles_with_two_cycles() {
+        let mut graph = Graph::<&str, i32>::new();
+        graph.insert_edge("a", "b", 1);
+        graph.insert_edge("b", "a", 2);
+        graph.insert_edge("b", "c", 3);
+        graph.insert_edge("c", "b", 4);
+
+        let cycles: HashSet<Vec<&str>> = all_elementary_cycles(&graph)
+            .into_iter()
+            .map(|mut cycle| {
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        assert_eq!(
+            cycles,
+            HashSet::from_iter([vec!["a", "b"], vec!["b", "c"]])
+        );
+    }
+
     #[test]
     fn break_cycles_with_empty_graph() {
         let mut graph = Graph::<&str, i32>::new();
-        break_cycles(&mut graph, |(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2));
+        break_cycles(
+            &mut graph,
+            &|node1, node2| node1.cmp(node2),
+            &|(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2),
+        );
         assert_eq!(graph.node_count(), 0);
     }
 
@@ -383,7 +855,11 @@ mod tests {
         graph.insert_edge("a", "b", 1);
         graph.insert_edge("b", "c", 2);
         graph.insert_edge("c", "d", 3);
-        break_cycles(&mut graph, |(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2));
+        break_cycles(
+            &mut graph,
+            &|node1, node2| node1.cmp(node2),
+            &|(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2),
+        );
         assert_eq!(graph.edge(&"a", &"b"), Some(&1));
         assert_eq!(graph.edge(&"b", &"c"), Some(&2));
         assert_eq!(graph.edge(&"c", &"d"), Some(&3));
@@ -395,7 +871,11 @@ mod tests {
         graph.insert_edge("a", "b", 1);
         graph.insert_edge("b", "c", 2);
         graph.insert_edge("c", "a", 3);
-        break_cycles(&mut graph, |(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2));
+        break_cycles(
+            &mut graph,
+            &|node1, node2| node1.cmp(node2),
+            &|(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2),
+        );
         assert_eq!(graph.edge(&"a", &"b"), Some(&1));
         assert_eq!(graph.edge(&"b", &"c"), Some(&2));
         assert_eq!(graph.edge(&"c", &"a"), None);
@@ -405,7 +885,11 @@ mod tests {
     fn break_cycles_with_self_cycle() {
         let mut graph = Graph::<&str, i32>::new();
         graph.insert_edge("a", "a", 1);
-        break_cycles(&mut graph, |(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2));
+        break_cycles(
+            &mut graph,
+            &|node1, node2| node1.cmp(node2),
+            &|(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2),
+        );
         assert_eq!(graph.edge(&"a", &"a"), None);
     }
 
@@ -450,7 +934,7 @@ mod tests {
                 |node1, node2| node1.cmp(node2),
                 |(_, _, edge1), (_, _, edge2)| edge1.cmp(edge2)
             ),
-            ["b", "c", "a"]
+            ["a", "b", "c"]
         );
     }
 
@@ -1,7 +1,17 @@
-use crate::checkers::ast::Checker;
-use ruff_diagnostics::{AutofixKind, Diagnostic, Violation};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use ruff_diagnostics::{AutofixKind, Diagnostic, Edit, Fix, Violation};
 use ruff_macros::{derive_message_formats, violation};
-use ruff_python_semantic::{Binding, Scope};
+use ruff_python_ast::Stmt;
+use ruff_python_trivia::CommentRanges;
+use ruff_source_file::Locator;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+
+use crate::checkers::ast::Checker;
+use crate::rules::ssort::graph::{topological_sort, Graph};
+use crate::rules::ssort::rules::organize_module_statements::UnsortedModuleStatements;
+use ruff_python_semantic::{Scope, ScopeKind};
 
 /// ## What it does
 /// Groups and sorts a statements based on the order in which they are referenced.
@@ -42,12 +52,352 @@ impl Violation for UnsortedStatements {
 pub(crate) fn organize_statements(
     checker: &Checker,
     scope: &Scope,
-    _diagnostics: &mut Vec<Diagnostic>,
+    diagnostics: &mut Vec<Diagnostic>,
 ) {
-    let bindings: Vec<(&str, &Binding<'_>)> = scope
-        .bindings()
-        .map(|(name, id)| (name, &checker.semantic().bindings[id]))
-        .filter(|(_, binding)| !binding.kind.is_builtin())
-        .collect();
-    println!("Bindings: {bindings:?}");
+    let Some(order) = sorted_statement_order(checker, scope) else {
+        return;
+    };
+
+    // Only flag the scope when the computed order actually differs from the
+    // source order; otherwise the statements are already organized.
+    if is_already_sorted(&order) {
+        return;
+    }
+
+    let range = TextRange::new(
+        order.iter().map(|stmt| stmt.range.start()).min().unwrap(),
+        order.iter().map(|stmt| stmt.range.end()).max().unwrap(),
+    );
+
+    // Emit the module-scoped violation at module scope, and the general one
+    // everywhere else; both carry the same reordering fix.
+    let mut diagnostic = if matches!(scope.kind, ScopeKind::Module) {
+        Diagnostic::new(UnsortedModuleStatements, range)
+    } else {
+        Diagnostic::new(UnsortedStatements, range)
+    };
+    diagnostic.set_fix(reorder_fix(checker.locator(), &order));
+    diagnostics.push(diagnostic);
+}
+
+/// Returns `true` when `order` already matches the source order, i.e. there is
+/// nothing to reorder.
+fn is_already_sorted(order: &[OrderedStatement]) -> bool {
+    order.iter().enumerate().all(|(i, stmt)| i == stmt.index)
+}
+
+/// A single top-level statement, tagged with its position in the source order.
+struct OrderedStatement {
+    /// The index of the statement in source order.
+    index: usize,
+    /// The full range of the statement, including leading comments and decorators.
+    range: TextRange,
+}
+
+/// Compute the dependency-respecting order of the top-level statements in
+/// `scope`, or `None` when there is nothing to sort.
+///
+/// We build a directed graph whose nodes are the top-level bindings and whose
+/// edges point from a defining statement to every statement that references
+/// it, then take a
+/// stable topological order that places each definition before its first use.
+/// Reference cycles (mutual recursion) are tolerated by [`topological_sort`],
+/// which breaks them by removing a greedy feedback arc set before sorting,
+/// preferring to cut the arcs that keep statements closest to their original
+/// source order.
+fn sorted_statement_order(checker: &Checker, scope: &Scope) -> Option<Vec<OrderedStatement>> {
+    let semantic = checker.semantic();
+
+    // Collect the top-level bindings, keyed by the statement that introduces
+    // them, preserving source order.
+    let mut statements: Vec<(TextRange, Vec<ruff_python_semantic::BindingId>)> = Vec::new();
+    let mut statement_index: HashMap<TextRange, usize> = HashMap::new();
+
+    for (_, id) in scope.bindings() {
+        let binding = &semantic.bindings[id];
+        if binding.kind.is_builtin() {
+            continue;
+        }
+        let Some(stmt) = binding.statement(semantic) else {
+            continue;
+        };
+        let range = statement_range(checker.locator(), checker.comment_ranges(), stmt);
+        let index = *statement_index.entry(range).or_insert_with(|| {
+            statements.push((range, Vec::new()));
+            statements.len() - 1
+        });
+        statements[index].1.push(id);
+    }
+
+    if statements.len() <= 1 {
+        return None;
+    }
+
+    // Sort the statements into source order so that node costs (and the fallback
+    // order for independent statements) are stable.
+    statements.sort_by_key(|(range, _)| range.start());
+    for (index, (range, _)) in statements.iter().enumerate() {
+        statement_index.insert(*range, index);
+    }
+
+    // Collect an edge for every reference, from the statement that introduces
+    // the binding to the statement that reads it, so `order_by_references`
+    // emits the referenced definition first.
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (def_index, (_, bindings)) in statements.iter().enumerate() {
+        for &id in bindings {
+            let binding = &semantic.bindings[id];
+            for reference_id in binding.references() {
+                let reference = semantic.reference(reference_id);
+                if let Some(user_index) = statement_containing(&statements, reference.start()) {
+                    if user_index != def_index {
+                        edges.push((def_index, user_index));
+                    }
+                }
+            }
+        }
+    }
+
+    let order = order_by_references(statements.len(), &edges);
+
+    Some(
+        order
+            .into_iter()
+            .map(|index| OrderedStatement {
+                index,
+                range: statements[index].0,
+            })
+            .collect(),
+    )
+}
+
+/// Topologically sort `count` statements (numbered `0..count` in source
+/// order) connected by `edges`, which are passed through verbatim to
+/// [`Graph::insert_edge`].
+///
+/// Node costs are tied to the statement index, so statements with no ordering
+/// constraint between them keep their original source order; edges carry no
+/// cost, so cycle breaking (see [`topological_sort`]) is order-stable too.
+fn order_by_references(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut graph = Graph::<usize, ()>::new();
+    for index in 0..count {
+        graph.insert_node(index);
+    }
+    for &(def_index, user_index) in edges {
+        graph.insert_edge(def_index, user_index, ());
+    }
+
+    topological_sort(
+        &graph,
+        |a: &usize, b: &usize| a.cmp(b),
+        |_: &(usize, usize, ()), _: &(usize, usize, ())| Ordering::Equal,
+    )
+}
+
+/// Find the index of the top-level statement whose range contains `offset`.
+fn statement_containing(
+    statements: &[(TextRange, Vec<ruff_python_semantic::BindingId>)],
+    offset: TextSize,
+) -> Option<usize> {
+    statements
+        .iter()
+        .position(|(range, _)| range.contains(offset))
+}
+
+/// Emit a [`Fix`] that rewrites the statement block into `order`.
+///
+/// We keep the source slots (the existing statement ranges, in source order) and
+/// fill each slot with the text of the statement that should occupy it, so only
+/// the moved statements produce edits.
+fn reorder_fix(locator: &Locator, order: &[OrderedStatement]) -> Fix {
+    let mut slots: Vec<TextRange> = order.iter().map(|stmt| stmt.range).collect();
+    slots.sort_by_key(TextRange::start);
+
+    let mut edits = slots
+        .iter()
+        .zip(order)
+        .filter(|(slot, stmt)| **slot != stmt.range)
+        .map(|(slot, stmt)| Edit::range_replacement(locator.slice(stmt.range).to_string(), *slot));
+
+    let first = edits
+        .next()
+        .expect("the order differs from the source, so at least one statement moves");
+    Fix::unsafe_edits(first, edits)
+}
+
+/// The full range of a statement, extended to cover its leading decorators and
+/// any standalone comments directly above it, so the fix moves the whole unit
+/// rather than detaching a comment from the statement it annotates.
+fn statement_range(locator: &Locator, comment_ranges: &CommentRanges, stmt: &Stmt) -> TextRange {
+    // Decorators precede the `def`/`class` keyword but live in a separate field,
+    // so fold the first decorator's start into the range.
+    let mut start = stmt.start();
+    match stmt {
+        Stmt::FunctionDef(function_def) => {
+            if let Some(decorator) = function_def.decorator_list.first() {
+                start = start.min(decorator.start());
+            }
+        }
+        Stmt::ClassDef(class_def) => {
+            if let Some(decorator) = class_def.decorator_list.first() {
+                start = start.min(decorator.start());
+            }
+        }
+        _ => {}
+    }
+
+    // Absorb own-line comments immediately above the statement, walking upwards
+    // while each comment is separated from the code only by whitespace.
+    for comment in comment_ranges.iter().rev() {
+        if comment.end() > start {
+            continue;
+        }
+        if !locator
+            .slice(TextRange::new(comment.end(), start))
+            .trim()
+            .is_empty()
+        {
+            break;
+        }
+        let line_start = locator.line_start(comment.start());
+        // Only standalone (own-line) comments belong to the statement.
+        if locator
+            .slice(TextRange::new(line_start, comment.start()))
+            .trim()
+            .is_empty()
+        {
+            start = line_start;
+        } else {
+            break;
+        }
+    }
+
+    TextRange::new(start, stmt.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ruff_python_parser::parse_suite;
+
+    /// Build a `TextRange` covering `needle` within `source`, assuming `needle`
+    /// occurs exactly once and runs to the end of its line.
+    fn range_of(source: &str, needle: &str) -> TextRange {
+        let start = source.find(needle).expect("needle present in source");
+        TextRange::new(
+            start.try_into().unwrap(),
+            (start + needle.len()).try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn order_by_references_with_forward_reference() {
+        // def h(): g()   -- index 0
+        // def f(): pass  -- index 1
+        // def g(): f()   -- index 2
+        //
+        // `f` is referenced from `g`, and `g` is referenced from `h`, so the
+        // dependency-respecting order places the definitions before their
+        // first use: f, g, h.
+        assert_eq!(order_by_references(3, &[(1, 2), (2, 0)]), [1, 2, 0]);
+    }
+
+    #[test]
+    fn order_by_references_with_mutual_recursion_is_stable() {
+        // Two statements that reference each other form a cycle with no
+        // preferred direction; the feedback-arc-set heuristic must still
+        // terminate, and since neither side outranks the other it leaves them
+        // in their original source order instead of churning the diff.
+        assert_eq!(order_by_references(2, &[(0, 1), (1, 0)]), [0, 1]);
+    }
+
+    #[test]
+    fn order_by_references_with_no_edges_keeps_source_order() {
+        assert_eq!(order_by_references(3, &[]), [0, 1, 2]);
+    }
+
+    #[test]
+    fn is_already_sorted_true_for_identity_order() {
+        let order = vec![
+            OrderedStatement {
+                index: 0,
+                range: TextRange::new(TextSize::from(0), TextSize::from(1)),
+            },
+            OrderedStatement {
+                index: 1,
+                range: TextRange::new(TextSize::from(1), TextSize::from(2)),
+            },
+        ];
+        assert!(is_already_sorted(&order));
+    }
+
+    #[test]
+    fn is_already_sorted_false_when_indices_are_permuted() {
+        let order = vec![
+            OrderedStatement {
+                index: 1,
+                range: TextRange::new(TextSize::from(1), TextSize::from(2)),
+            },
+            OrderedStatement {
+                index: 0,
+                range: TextRange::new(TextSize::from(0), TextSize::from(1)),
+            },
+        ];
+        assert!(!is_already_sorted(&order));
+    }
+
+    #[test]
+    fn statement_range_absorbs_decorator() {
+        let source = "@decorator\ndef f():\n    pass\n";
+        let stmts = parse_suite(source).expect("valid Python");
+        let locator = Locator::new(source);
+        let comment_ranges = CommentRanges::new(Vec::new());
+
+        let range = statement_range(&locator, &comment_ranges, &stmts[0]);
+
+        assert_eq!(locator.slice(range), source.trim_end());
+    }
+
+    #[test]
+    fn statement_range_absorbs_leading_comment_separated_by_blank_line() {
+        let source = "x = 1\n\n# about f\ndef f():\n    pass\n";
+        let stmts = parse_suite(source).expect("valid Python");
+        let locator = Locator::new(source);
+        let comment_ranges = CommentRanges::new(vec![range_of(source, "# about f")]);
+
+        let range = statement_range(&locator, &comment_ranges, &stmts[1]);
+
+        assert_eq!(locator.slice(range), "# about f\ndef f():\n    pass");
+    }
+
+    #[test]
+    fn statement_range_absorbs_comment_with_no_blank_line_between_statements() {
+        // `x = 1` and the comment above `f` sit on adjacent lines with no blank
+        // line to disambiguate which statement the comment belongs to;
+        // `statement_range` resolves the ambiguity by always attaching a
+        // standalone comment to the statement immediately below it.
+        let source = "x = 1\n# about f\ndef f():\n    pass\n";
+        let stmts = parse_suite(source).expect("valid Python");
+        let locator = Locator::new(source);
+        let comment_ranges = CommentRanges::new(vec![range_of(source, "# about f")]);
+
+        let range = statement_range(&locator, &comment_ranges, &stmts[1]);
+
+        assert_eq!(locator.slice(range), "# about f\ndef f():\n    pass");
+    }
+
+    #[test]
+    fn statement_range_does_not_absorb_trailing_comment() {
+        // The comment trails `x = 1` on the same line, so it is not own-line
+        // and must stay attached to the statement above it.
+        let source = "x = 1  # not about f\ndef f():\n    pass\n";
+        let stmts = parse_suite(source).expect("valid Python");
+        let locator = Locator::new(source);
+        let comment_ranges = CommentRanges::new(vec![range_of(source, "# not about f")]);
+
+        let range = statement_range(&locator, &comment_ranges, &stmts[1]);
+
+        assert_eq!(locator.slice(range), "def f():\n    pass");
+    }
 }
@@ -1,9 +1,9 @@
 use ruff_diagnostics::{AlwaysFixableViolation, Violation};
 use ruff_diagnostics::{Diagnostic, Edit, Fix};
 use ruff_macros::{derive_message_formats, violation};
-use ruff_python_ast::docstrings::{clean_space, leading_space};
+use ruff_python_ast::docstrings::leading_space;
 use ruff_source_file::NewlineWithTrailingNewline;
-use ruff_text_size::{Ranged, TextSize};
+use ruff_text_size::Ranged;
 use ruff_text_size::{TextLen, TextRange};
 
 use crate::checkers::ast::Checker;
@@ -40,9 +40,6 @@ use crate::registry::Rule;
 /// We recommend against using this rule alongside the [formatter]. The
 /// formatter enforces consistent indentation, making the rule redundant.
 ///
-/// The rule is also incompatible with the [formatter] when using
-/// `format.indent-style="tab"`.
-///
 /// ## References
 /// - [PEP 257 – Docstring Conventions](https://peps.python.org/pep-0257/)
 /// - [NumPy Style Guide](https://numpydoc.readthedocs.io/en/latest/format.html)
@@ -159,9 +156,129 @@ impl AlwaysFixableViolation for OverIndentation {
     }
 }
 
+/// The indentation unit used by a source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndentStyle {
+    Tabs,
+    Spaces(u8),
+}
+
+/// Attempt to detect the indentation unit used by `source`.
+///
+/// This mirrors Helix's `auto_detect_indent_style`: we scan the leading
+/// whitespace of the first non-blank lines and, whenever indentation increases
+/// relative to the previous non-blank line, record the size of the increase as a
+/// vote in a histogram keyed by width `1..=8` (plus a separate tab counter). The
+/// width with the most votes wins, falling back to [`IndentStyle::Tabs`] when tab
+/// votes dominate. We return `None` when the sample is inconclusive so callers
+/// preserve their existing behavior.
+fn auto_detect_indent_style(source: &str) -> Option<IndentStyle> {
+    /// The number of non-blank lines we inspect before giving up.
+    const MAX_LINES: usize = 200;
+
+    // Votes for space widths `1..=8`; index `0` is unused.
+    let mut space_votes = [0usize; 9];
+    let mut tab_votes = 0usize;
+
+    let mut prev_spaces: Option<usize> = None;
+    for line in source
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_LINES)
+    {
+        let leading = leading_space(line);
+        if leading.contains('\t') {
+            tab_votes += 1;
+        }
+
+        let spaces = leading.chars().filter(|c| *c == ' ').count();
+        if let Some(prev) = prev_spaces {
+            if spaces > prev {
+                let delta = spaces - prev;
+                if (1..=8).contains(&delta) {
+                    space_votes[delta] += 1;
+                }
+            }
+        }
+        prev_spaces = Some(spaces);
+    }
+
+    let (best_width, best_votes) = space_votes
+        .iter()
+        .enumerate()
+        .skip(1)
+        .max_by_key(|(_, votes)| **votes)
+        .map(|(width, votes)| (width as u8, *votes))
+        .unwrap_or((0, 0));
+
+    if tab_votes > best_votes {
+        Some(IndentStyle::Tabs)
+    } else if best_votes > 0 {
+        Some(IndentStyle::Spaces(best_width))
+    } else {
+        None
+    }
+}
+
+/// Compute the visual column spanned by the leading whitespace `indent`,
+/// expanding each tab to the next multiple of `tab_size` and counting every
+/// other whitespace character as a single column.
+///
+/// This mirrors Helix's `indent_level_for_line`: it lets the rules reason about
+/// indentation in the columns a reader actually sees rather than raw byte or
+/// character counts, so tab- and mixed-indented docstrings are handled correctly.
+fn visual_width(indent: &str, tab_size: usize) -> usize {
+    let mut column = 0;
+    for ch in indent.chars() {
+        if ch == '\t' && tab_size > 0 {
+            column += tab_size - (column % tab_size);
+        } else {
+            column += 1;
+        }
+    }
+    column
+}
+
+/// Snap a visual column to the nearest multiple of the detected indentation unit.
+///
+/// When detection is inconclusive (or the file is tab-indented) the column is
+/// returned unchanged, preserving the opening-quote alignment as before.
+fn snap_column(column: usize, style: Option<IndentStyle>) -> usize {
+    match style {
+        Some(IndentStyle::Spaces(width)) if width > 0 => {
+            let width = usize::from(width);
+            ((column + width / 2) / width) * width
+        }
+        _ => column,
+    }
+}
+
+/// Render an indentation string reaching visual column `column`, emitting the
+/// tab/space mix implied by `style` (a whole run of tabs plus a spaces remainder
+/// for a tab-indented file, spaces otherwise).
+fn render_indent(column: usize, style: Option<IndentStyle>, tab_size: usize) -> String {
+    match style {
+        Some(IndentStyle::Tabs) if tab_size > 0 => {
+            let tabs = column / tab_size;
+            let spaces = column % tab_size;
+            format!("{}{}", "\t".repeat(tabs), " ".repeat(spaces))
+        }
+        _ => " ".repeat(column),
+    }
+}
+
 /// D206, D207, D208
 pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
     let body = docstring.body();
+    let mut indent_style = auto_detect_indent_style(checker.locator().contents());
+    let tab_size = checker.settings.tab_size.as_usize();
+
+    // D206 (`IndentWithSpaces`) flags tab indentation, so emitting tabs in the
+    // D207/D208 fixes would make the two rules fight over the same lines. When
+    // D206 is enabled, render the fixes with spaces instead.
+    if checker.enabled(Rule::IndentWithSpaces) && matches!(indent_style, Some(IndentStyle::Tabs)) {
+        indent_style = None;
+    }
 
     // Split the docstring into lines.
     let lines: Vec<_> = NewlineWithTrailingNewline::with_offset(&body, body.start()).collect();
@@ -169,6 +286,11 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         return;
     }
 
+    // The visual column of the opening quote, the baseline every continuation line
+    // is compared against. We reason in visual columns (tabs expanded to
+    // `tab_size`) so tab- and mixed-indented docstrings compare correctly.
+    let docstring_indent = visual_width(docstring.indentation, tab_size);
+
     let mut has_seen_tab = docstring.indentation.contains('\t');
     let mut is_over_indented = true;
     let mut over_indented_lines = vec![];
@@ -189,6 +311,7 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         }
 
         let line_indent = leading_space(line);
+        let line_indent_width = visual_width(line_indent, tab_size);
 
         // We only report tab indentation once, so only check if we haven't seen a tab
         // yet.
@@ -197,13 +320,14 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         if checker.enabled(Rule::UnderIndentation) {
             // We report under-indentation on every line. This isn't great, but enables
             // fix.
-            if (i == lines.len() - 1 || !is_blank)
-                && line_indent.len() < docstring.indentation.len()
-            {
+            if (i == lines.len() - 1 || !is_blank) && line_indent_width < docstring_indent {
                 let mut diagnostic =
                     Diagnostic::new(UnderIndentation, TextRange::empty(line.start()));
+                // Align to the opening-quote column exactly; the baseline itself
+                // must not be snapped, only relative interior indentation is.
+                let indent = render_indent(docstring_indent, indent_style, tab_size);
                 diagnostic.set_fix(Fix::safe_edit(Edit::range_replacement(
-                    clean_space(docstring.indentation),
+                    indent,
                     TextRange::at(line.start(), line_indent.text_len()),
                 )));
                 checker.diagnostics.push(diagnostic);
@@ -217,14 +341,12 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         // until we've viewed all the lines, so for now, just track
         // the over-indentation status of every line.
         if i < lines.len() - 1 {
-            if line_indent.len() > docstring.indentation.len() {
+            if line_indent_width > docstring_indent {
                 over_indented_lines.push(line);
 
-                // Track the _smallest_ offset we see, in terms of characters.
-                over_indented_offset = std::cmp::min(
-                    line_indent.chars().count() - docstring.indentation.chars().count(),
-                    over_indented_offset,
-                );
+                // Track the _smallest_ offset we see, in terms of visual columns.
+                over_indented_offset =
+                    std::cmp::min(line_indent_width - docstring_indent, over_indented_offset);
             } else {
                 is_over_indented = false;
             }
@@ -244,27 +366,27 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         if is_over_indented {
             for line in over_indented_lines {
                 let line_indent = leading_space(line);
-                let indent = clean_space(docstring.indentation);
+                let line_indent_width = visual_width(line_indent, tab_size);
+
+                // Strip the smallest common over-indentation (in visual columns)
+                // from every line, preserving any deeper relative indentation, and
+                // re-render the result in the target tab/space mix. Only the
+                // relative offset beyond the baseline is snapped to the unit; the
+                // baseline stays aligned to the opening quotes.
+                let stripped = line_indent_width - over_indented_offset;
+                let relative = stripped.saturating_sub(docstring_indent);
+                let target = docstring_indent + snap_column(relative, indent_style);
+                let indent = render_indent(target, indent_style, tab_size);
+                let range = TextRange::at(line.start(), line_indent.text_len());
 
                 // We report over-indentation on every line. This isn't great, but
                 // enables the fix capability.
                 let mut diagnostic =
                     Diagnostic::new(OverIndentation, TextRange::empty(line.start()));
                 let edit = if indent.is_empty() {
-                    Edit::deletion(line.start(), line_indent.text_len())
+                    Edit::range_deletion(range)
                 } else {
-                    // Convert the character count to an offset within the source.
-                    let offset = checker
-                        .locator()
-                        .after(line.start() + indent.text_len())
-                        .chars()
-                        .take(over_indented_offset)
-                        .map(TextLen::text_len)
-                        .sum::<TextSize>();
-                    Edit::range_replacement(
-                        indent.clone(),
-                        TextRange::at(line.start(), indent.text_len() + offset),
-                    )
+                    Edit::range_replacement(indent, range)
                 };
                 diagnostic.set_fix(Fix::safe_edit(edit));
                 checker.diagnostics.push(diagnostic);
@@ -274,10 +396,12 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
         // If the last line is over-indented...
         if let Some(last) = lines.last() {
             let line_indent = leading_space(last);
-            if line_indent.len() > docstring.indentation.len() {
+            if visual_width(line_indent, tab_size) > docstring_indent {
                 let mut diagnostic =
                     Diagnostic::new(OverIndentation, TextRange::empty(last.start()));
-                let indent = clean_space(docstring.indentation);
+                // The closing quotes align to the opening-quote column; never snap
+                // the baseline away from it.
+                let indent = render_indent(docstring_indent, indent_style, tab_size);
                 let range = TextRange::at(last.start(), line_indent.text_len());
                 let edit = if indent.is_empty() {
                     Edit::range_deletion(range)
@@ -289,4 +413,337 @@ pub(crate) fn indent(checker: &mut Checker, docstring: &Docstring) {
             }
         }
     }
+
+    // Validate the indentation of any code blocks embedded in the docstring,
+    // reusing the (possibly overridden) style computed above.
+    code_block_indentation(checker, docstring, indent_style, tab_size);
+}
+
+/// ## What it does
+/// Checks for inconsistent indentation of code blocks embedded in docstrings.
+///
+/// ## Why is this bad?
+/// Doctest prompts, reStructuredText literal blocks, and Markdown fenced code
+/// blocks are often copied verbatim into documentation or executed as tests.
+/// When their lines are indented inconsistently the sample stops reading (and,
+/// for doctests, stops running) as intended. The [`over-indentation`] and
+/// [`under-indentation`] rules only compare each line against a single docstring
+/// baseline and do not check the lines of a block against each other, so the
+/// inconsistency goes unnoticed.
+///
+/// ## Example
+/// ```python
+/// def f():
+///     """Example.
+///
+///     >>> f()
+///       >>> g()
+///     """
+/// ```
+///
+/// Use instead:
+/// ```python
+/// def f():
+///     """Example.
+///
+///     >>> f()
+///     >>> g()
+///     """
+/// ```
+///
+/// ## References
+/// - [PEP 257 – Docstring Conventions](https://peps.python.org/pep-0257/)
+///
+/// [`over-indentation`]: https://docs.astral.sh/ruff/rules/over-indentation/
+/// [`under-indentation`]: https://docs.astral.sh/ruff/rules/under-indentation/
+#[violation]
+pub struct InconsistentCodeBlockIndentation;
+
+impl AlwaysFixableViolation for InconsistentCodeBlockIndentation {
+    #[derive_message_formats]
+    fn message(&self) -> String {
+        format!("Code block in docstring is inconsistently indented")
+    }
+
+    fn fix_title(&self) -> String {
+        "Re-align code block".to_string()
+    }
+}
+
+/// The kind of embedded code block a line opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeBlock {
+    /// A Markdown fence (```` ``` ```` or `~~~`), closed by a matching fence.
+    Fence,
+    /// A run of doctest `>>> `/`... ` prompts.
+    Doctest,
+    /// A reStructuredText literal block introduced by a trailing `::` or a
+    /// `.. code-block::` directive; the block is the following indented lines.
+    Rest,
+}
+
+/// The indentation unit, in visual columns, implied by the detected style.
+fn unit_width(style: Option<IndentStyle>, tab_size: usize) -> usize {
+    match style {
+        Some(IndentStyle::Spaces(width)) if width > 0 => usize::from(width),
+        Some(IndentStyle::Tabs) if tab_size > 0 => tab_size,
+        _ => 4,
+    }
+}
+
+/// Checks the indentation of code blocks embedded in a docstring body.
+///
+/// `indent_style` and `tab_size` are taken from [`indent`], which may have
+/// overridden the auto-detected style (e.g. to avoid fighting with D206 over
+/// tabs vs. spaces); recomputing them here would lose that override.
+pub(crate) fn code_block_indentation(
+    checker: &mut Checker,
+    docstring: &Docstring,
+    indent_style: Option<IndentStyle>,
+    tab_size: usize,
+) {
+    if !checker.enabled(Rule::InconsistentCodeBlockIndentation) {
+        return;
+    }
+
+    let body = docstring.body();
+    let unit = unit_width(indent_style, tab_size);
+
+    let lines: Vec<_> = NewlineWithTrailingNewline::with_offset(&body, body.start()).collect();
+    let text_lines: Vec<&str> = lines.iter().map(|line| &line[..]).collect();
+
+    for (kind, base, block) in find_code_blocks(&text_lines, tab_size, unit) {
+        align_block(checker, &lines, &block, kind, base, unit, indent_style, tab_size);
+    }
+}
+
+/// Scan `lines` (a docstring body's lines) for embedded code blocks, returning
+/// each block's kind, the column its interior should align to, and the
+/// indices (into `lines`) of its interior lines.
+///
+/// Pulled out of [`code_block_indentation`] as a pure function so the
+/// doctest/fence/reST-literal detection can be unit tested without needing a
+/// [`Checker`] or [`Docstring`].
+fn find_code_blocks(
+    lines: &[&str],
+    tab_size: usize,
+    unit: usize,
+) -> Vec<(CodeBlock, usize, Vec<usize>)> {
+    let mut blocks = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+
+        // Identify the start of a code block and the column it should align to.
+        let (kind, base) = if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            (CodeBlock::Fence, visual_width(leading_space(line), tab_size))
+        } else if trimmed.starts_with(">>> ") || trimmed == ">>>" {
+            (CodeBlock::Doctest, visual_width(leading_space(line), tab_size))
+        } else if trimmed.ends_with("::") || trimmed.starts_with(".. code-block::") {
+            // The literal block is indented one unit beyond its introducer.
+            (
+                CodeBlock::Rest,
+                visual_width(leading_space(line), tab_size) + unit,
+            )
+        } else {
+            i += 1;
+            continue;
+        };
+
+        // Collect the interior lines of the block.
+        let mut block: Vec<usize> = Vec::new();
+        i += 1;
+        while i < lines.len() {
+            let current = lines[i];
+            let is_blank = current.trim().is_empty();
+            match kind {
+                CodeBlock::Fence => {
+                    // Stop at the docstring's last line even without a closing
+                    // fence, so a forgotten closing marker doesn't swallow the
+                    // line holding the closing quotes into the block.
+                    if i == lines.len() - 1 {
+                        break;
+                    }
+                    let t = current.trim_start();
+                    block.push(i);
+                    i += 1;
+                    // Only a fence of the same kind as the opener closes it.
+                    if t.starts_with(&trimmed[..3]) {
+                        break;
+                    }
+                }
+                CodeBlock::Doctest => {
+                    let t = current.trim_start();
+                    if t.starts_with(">>> ") || t.starts_with("... ") || t == ">>>" || t == "..." {
+                        block.push(i);
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                CodeBlock::Rest => {
+                    if is_blank {
+                        block.push(i);
+                        i += 1;
+                    } else if visual_width(leading_space(current), tab_size) >= base {
+                        block.push(i);
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        blocks.push((kind, base, block));
+    }
+
+    blocks
+}
+
+/// Flag and fix any line in `block` whose indentation is inconsistent with the
+/// block's base column.
+fn align_block(
+    checker: &mut Checker,
+    lines: &[impl Ranged + std::ops::Deref<Target = str>],
+    block: &[usize],
+    kind: CodeBlock,
+    base: usize,
+    unit: usize,
+    indent_style: Option<IndentStyle>,
+    tab_size: usize,
+) {
+    for &index in block {
+        let line = &lines[index];
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let line_indent = leading_space(line);
+        let width = visual_width(line_indent, tab_size);
+
+        // The expected column snaps the line's indentation to the block base plus
+        // a whole number of units of interior structure. Doctest prompts carry
+        // their structure after the prompt, so every prompt sits at the base.
+        let relative = width.saturating_sub(base);
+        let snapped_relative = if kind == CodeBlock::Doctest {
+            0
+        } else {
+            ((relative + unit / 2) / unit) * unit
+        };
+        let target = base + snapped_relative;
+        if target == width {
+            continue;
+        }
+
+        let mut diagnostic =
+            Diagnostic::new(InconsistentCodeBlockIndentation, TextRange::empty(line.start()));
+        let indent = render_indent(target, indent_style, tab_size);
+        let range = TextRange::at(line.start(), line_indent.text_len());
+        let edit = if indent.is_empty() {
+            Edit::range_deletion(range)
+        } else {
+            Edit::range_replacement(indent, range)
+        };
+        diagnostic.set_fix(Fix::safe_edit(edit));
+        checker.diagnostics.push(diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn auto_detect_indent_style_space_histogram() {
+        // Every indentation increase is by 4 spaces, so 4 should win the vote.
+        let source = "def f():\n    a = 1\n    if a:\n        b = 2\n";
+        assert_eq!(
+            auto_detect_indent_style(source),
+            Some(IndentStyle::Spaces(4))
+        );
+    }
+
+    #[test]
+    fn auto_detect_indent_style_tab_dominant() {
+        let source = "def f():\n\ta = 1\n\tif a:\n\t\tb = 2\n";
+        assert_eq!(auto_detect_indent_style(source), Some(IndentStyle::Tabs));
+    }
+
+    #[test]
+    fn auto_detect_indent_style_inconclusive() {
+        // No line is ever more indented than the previous one, so there are no
+        // votes to tally.
+        let source = "a = 1\nb = 2\nc = 3\n";
+        assert_eq!(auto_detect_indent_style(source), None);
+    }
+
+    #[test]
+    fn visual_width_expands_tabs_to_tab_size() {
+        // A tab advances to the next multiple of `tab_size`; a second tab then
+        // advances a full `tab_size` again from that column.
+        assert_eq!(visual_width("\t", 4), 4);
+        assert_eq!(visual_width("\t\t", 4), 8);
+        assert_eq!(visual_width("  \t", 4), 4);
+    }
+
+    #[test]
+    fn render_indent_renders_tabs_then_space_remainder() {
+        assert_eq!(
+            render_indent(10, Some(IndentStyle::Tabs), 4),
+            "\t\t  ".to_string()
+        );
+        assert_eq!(
+            render_indent(10, Some(IndentStyle::Spaces(4)), 4),
+            " ".repeat(10)
+        );
+    }
+
+    #[test]
+    fn find_code_blocks_fence() {
+        let lines = ["Example.", "```", "f()", "```", "Done."];
+        let blocks = find_code_blocks(&lines, 4, 4);
+        assert_eq!(blocks.len(), 1);
+        let (kind, base, block) = &blocks[0];
+        assert_eq!(*kind, CodeBlock::Fence);
+        assert_eq!(*base, 0);
+        assert_eq!(block, &vec![2, 3]);
+    }
+
+    #[test]
+    fn find_code_blocks_fence_unterminated() {
+        // No closing fence: the block must stop before the docstring's last
+        // line rather than absorbing it.
+        let lines = ["Example.", "```", "f()", "g()"];
+        let blocks = find_code_blocks(&lines, 4, 4);
+        assert_eq!(blocks.len(), 1);
+        let (kind, _base, block) = &blocks[0];
+        assert_eq!(*kind, CodeBlock::Fence);
+        assert_eq!(block, &vec![2]);
+    }
+
+    #[test]
+    fn find_code_blocks_doctest() {
+        let lines = ["Example.", ">>> f()", ">>> g()", "Done."];
+        let blocks = find_code_blocks(&lines, 4, 4);
+        assert_eq!(blocks.len(), 1);
+        let (kind, base, block) = &blocks[0];
+        assert_eq!(*kind, CodeBlock::Doctest);
+        assert_eq!(*base, 0);
+        assert_eq!(block, &vec![2]);
+    }
+
+    #[test]
+    fn find_code_blocks_rest() {
+        let lines = ["Example::", "", "    f()", "    g()", "Done."];
+        let blocks = find_code_blocks(&lines, 4, 4);
+        assert_eq!(blocks.len(), 1);
+        let (kind, base, block) = &blocks[0];
+        assert_eq!(*kind, CodeBlock::Rest);
+        assert_eq!(*base, 4);
+        assert_eq!(block, &vec![1, 2, 3]);
+    }
 }